@@ -0,0 +1,272 @@
+//! Transitive resolution of the cross-bindle dependencies declared in a
+//! [`BindleSpec`](crate::BindleSpec)'s `dependencies` list.
+//!
+//! Unlike [`crate::resolver`], which picks an activation set of *parcels* within a
+//! single invoice, this module walks *across* invoices: given a root invoice and a
+//! place to look things up, it finds the highest non-yanked version of each
+//! dependency that satisfies its requirement, and recurses into that dependency's
+//! own dependencies.
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::pin::Pin;
+
+use thiserror::Error;
+
+use crate::storage::{Storage, StorageError};
+use crate::Invoice;
+
+/// An error produced while resolving an invoice's cross-bindle dependencies.
+#[derive(Error, Debug)]
+pub enum DependencyError {
+    /// No stored, non-yanked version of `name` satisfies `requirement`.
+    #[error("no version of '{name}' satisfies requirement '{requirement}'")]
+    UnsatisfiedDependency { name: String, requirement: String },
+    /// The dependency graph is cyclic: `name` depends (transitively) on itself.
+    #[error("dependency cycle detected at '{0}'")]
+    Cycle(String),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+/// Resolves `root`'s dependency closure against `storage`.
+///
+/// Returns a flattened, de-duplicated list of the invoices (not including `root`
+/// itself) that must be present to deploy `root`, in the order they were first
+/// resolved.
+pub async fn resolve<S: Storage + Send + Sync>(
+    root: &Invoice,
+    storage: &S,
+) -> Result<Vec<Invoice>, DependencyError> {
+    let mut closure = Vec::new();
+    let mut resolved_names: HashSet<String> = HashSet::new();
+    let mut in_progress: HashSet<String> = HashSet::new();
+    in_progress.insert(root.bindle.id.name().to_owned());
+
+    resolve_into(root, storage, &mut closure, &mut resolved_names, &mut in_progress).await?;
+    Ok(closure)
+}
+
+fn resolve_into<'a, S: Storage + Send + Sync>(
+    invoice: &'a Invoice,
+    storage: &'a S,
+    closure: &'a mut Vec<Invoice>,
+    resolved_names: &'a mut HashSet<String>,
+    in_progress: &'a mut HashSet<String>,
+) -> Pin<Box<dyn Future<Output = Result<(), DependencyError>> + 'a>> {
+    Box::pin(async move {
+        let dependencies = match &invoice.bindle.dependencies {
+            Some(dependencies) => dependencies,
+            None => return Ok(()),
+        };
+
+        for dependency in dependencies {
+            if resolved_names.contains(&dependency.name) {
+                continue;
+            }
+            if !in_progress.insert(dependency.name.clone()) {
+                return Err(DependencyError::Cycle(dependency.name.clone()));
+            }
+
+            let candidates = storage.get_invoice_versions(&dependency.name).await?;
+            let best = candidates
+                .into_iter()
+                .filter(|id| dependency.version.matches(id.version()))
+                .max_by(|a, b| a.version().cmp(b.version()))
+                .ok_or_else(|| DependencyError::UnsatisfiedDependency {
+                    name: dependency.name.clone(),
+                    requirement: dependency.version.to_string(),
+                })?;
+
+            let dep_invoice = storage.get_invoice(best.to_string()).await?;
+            resolve_into(&dep_invoice, storage, closure, resolved_names, in_progress).await?;
+
+            resolved_names.insert(dependency.name.clone());
+            in_progress.remove(&dependency.name);
+            closure.push(dep_invoice);
+        }
+
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::convert::TryInto;
+
+    use tokio::io::AsyncRead;
+
+    use super::*;
+    use crate::id::ParseError;
+    use crate::storage::Result;
+    use crate::{BindleSpec, Dependency, Id, Invoice, Label};
+
+    struct MockStorage {
+        invoices: Vec<Invoice>,
+    }
+
+    #[async_trait::async_trait]
+    impl Storage for MockStorage {
+        async fn create_invoice(&self, _inv: &Invoice) -> Result<Vec<Label>> {
+            unimplemented!("not needed for dependency resolution tests")
+        }
+
+        async fn get_invoice<I>(&self, id: I) -> Result<Invoice>
+        where
+            I: TryInto<Id, Error = ParseError> + Send,
+        {
+            let id: Id = id.try_into()?;
+            self.invoices
+                .iter()
+                .find(|inv| inv.bindle.id == id)
+                .cloned()
+                .ok_or(StorageError::NotFound)
+        }
+
+        async fn get_yanked_invoice<I>(&self, id: I) -> Result<Invoice>
+        where
+            I: TryInto<Id, Error = ParseError> + Send,
+        {
+            self.get_invoice(id).await
+        }
+
+        async fn yank_invoice<I>(&self, _id: I) -> Result<()>
+        where
+            I: TryInto<Id, Error = ParseError> + Send,
+        {
+            unimplemented!("not needed for dependency resolution tests")
+        }
+
+        async fn get_invoice_versions(&self, name: &str) -> Result<Vec<Id>> {
+            Ok(self
+                .invoices
+                .iter()
+                .filter(|inv| inv.bindle.id.name() == name && !inv.yanked.unwrap_or(false))
+                .map(|inv| inv.bindle.id.clone())
+                .collect())
+        }
+
+        async fn create_parcel<R: AsyncRead + Unpin + Send + Sync>(
+            &self,
+            _label: &Label,
+            _data: &mut R,
+        ) -> Result<()> {
+            unimplemented!("not needed for dependency resolution tests")
+        }
+
+        async fn get_parcel(&self, _parcel_id: &str) -> Result<Box<dyn AsyncRead + Unpin + Send>> {
+            unimplemented!("not needed for dependency resolution tests")
+        }
+
+        async fn get_label(&self, _parcel_id: &str) -> Result<Label> {
+            unimplemented!("not needed for dependency resolution tests")
+        }
+    }
+
+    fn invoice(id: &str, dependencies: Option<Vec<Dependency>>) -> Invoice {
+        Invoice {
+            bindle_version: crate::BINDLE_VERSION_1.to_owned(),
+            yanked: None,
+            bindle: BindleSpec {
+                id: id.parse().unwrap(),
+                description: None,
+                authors: None,
+                dependencies,
+            },
+            annotations: None,
+            parcels: None,
+            group: None,
+            signatures: None,
+        }
+    }
+
+    fn dependency(name: &str, version: &str) -> Dependency {
+        Dependency {
+            name: name.to_owned(),
+            version: version.parse().unwrap(),
+            repositories: None,
+            annotations: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolves_direct_dependency() {
+        let storage = MockStorage {
+            invoices: vec![invoice("lib/1.2.0", None)],
+        };
+        let root = invoice("app/1.0.0", Some(vec![dependency("lib", "^1.0")]));
+
+        let closure = resolve(&root, &storage).await.unwrap();
+        assert_eq!(closure.len(), 1);
+        assert_eq!(closure[0].bindle.id.name(), "lib");
+    }
+
+    #[tokio::test]
+    async fn test_picks_highest_satisfying_version() {
+        let storage = MockStorage {
+            invoices: vec![
+                invoice("lib/1.2.0", None),
+                invoice("lib/1.5.0", None),
+                invoice("lib/2.0.0", None),
+            ],
+        };
+        let root = invoice("app/1.0.0", Some(vec![dependency("lib", "^1.0")]));
+
+        let closure = resolve(&root, &storage).await.unwrap();
+        assert_eq!(closure.len(), 1);
+        assert_eq!(closure[0].bindle.id.version().to_string(), "1.5.0");
+    }
+
+    #[tokio::test]
+    async fn test_resolves_transitive_dependencies() {
+        let storage = MockStorage {
+            invoices: vec![
+                invoice("lib/1.0.0", Some(vec![dependency("base", "^1.0")])),
+                invoice("base/1.0.0", None),
+            ],
+        };
+        let root = invoice("app/1.0.0", Some(vec![dependency("lib", "^1.0")]));
+
+        let closure = resolve(&root, &storage).await.unwrap();
+        let names: Vec<_> = closure.iter().map(|i| i.bindle.id.name().to_owned()).collect();
+        assert_eq!(names, vec!["base", "lib"]);
+    }
+
+    #[tokio::test]
+    async fn test_unsatisfied_dependency_errors() {
+        let storage = MockStorage {
+            invoices: vec![invoice("lib/0.9.0", None)],
+        };
+        let root = invoice("app/1.0.0", Some(vec![dependency("lib", "^1.0")]));
+
+        let err = resolve(&root, &storage).await.unwrap_err();
+        assert!(matches!(err, DependencyError::UnsatisfiedDependency { name, .. } if name == "lib"));
+    }
+
+    #[tokio::test]
+    async fn test_yanked_versions_are_ignored() {
+        let mut yanked = invoice("lib/2.0.0", None);
+        yanked.yanked = Some(true);
+        let storage = MockStorage {
+            invoices: vec![invoice("lib/1.0.0", None), yanked],
+        };
+        let root = invoice("app/1.0.0", Some(vec![dependency("lib", "*")]));
+
+        let closure = resolve(&root, &storage).await.unwrap();
+        assert_eq!(closure[0].bindle.id.version().to_string(), "1.0.0");
+    }
+
+    #[tokio::test]
+    async fn test_dependency_cycle_errors() {
+        let storage = MockStorage {
+            invoices: vec![
+                invoice("a/1.0.0", Some(vec![dependency("b", "^1.0")])),
+                invoice("b/1.0.0", Some(vec![dependency("a", "^1.0")])),
+            ],
+        };
+        let root = invoice("app/1.0.0", Some(vec![dependency("a", "^1.0")]));
+
+        let err = resolve(&root, &storage).await.unwrap_err();
+        assert!(matches!(err, DependencyError::Cycle(_)));
+    }
+}
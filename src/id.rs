@@ -0,0 +1,230 @@
+//! The `Id` of a bindle: a name paired with an exact, parsed SemVer version.
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+use semver::{Compat, Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// The unique name and version of a bindle.
+///
+/// `version` is kept as an already-parsed [`semver::Version`] rather than a raw
+/// string, so a bindle with an unparseable version fails to parse at all instead of
+/// silently failing to match any [`VersionRequirement`] later on.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct Id {
+    name: String,
+    #[serde(with = "version_as_string")]
+    version: Version,
+}
+
+impl Id {
+    /// The bindle's name.
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// The bindle's exact version.
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    /// A repeatable, opaque, filesystem-safe name derived from `name` and `version`.
+    ///
+    /// This is used as the canonical on-disk/storage name for an invoice, so that
+    /// the name and version never have to survive a trip through a path or URL
+    /// unescaped.
+    pub fn sha(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.name.as_bytes());
+        hasher.update(b"/");
+        hasher.update(self.version.to_string().as_bytes());
+        hex::encode(hasher.finalize())
+    }
+}
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.name, self.version)
+    }
+}
+
+impl FromStr for Id {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, version) = s.rsplit_once('/').ok_or(ParseError::InvalidId)?;
+        if name.is_empty() {
+            return Err(ParseError::InvalidId);
+        }
+        let version = Version::parse(version).map_err(|_| ParseError::InvalidId)?;
+        Ok(Id {
+            name: name.to_owned(),
+            version,
+        })
+    }
+}
+
+impl TryFrom<String> for Id {
+    type Error = ParseError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl TryFrom<&str> for Id {
+    type Error = ParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// (De)serializes a `semver::Version` as its string representation, so an `Id`'s
+/// `version` field round-trips through TOML/JSON as plain text like every other
+/// version string bindle writes to the wire.
+mod version_as_string {
+    use semver::Version;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S>(version: &Version, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        version.to_string().serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D>(deserializer: D) -> Result<Version, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        Version::parse(&raw).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A SemVer version *requirement* (a range), as distinct from the exact
+/// [`semver::Version`] on an [`Id`].
+///
+/// A known name plus an exact version is a fundamentally different thing from a
+/// requirement that version must satisfy, so the two are never represented by the
+/// same type even though they're both ultimately backed by the `semver` crate.
+#[derive(Debug, Clone)]
+pub struct VersionRequirement(VersionReq);
+
+impl VersionRequirement {
+    /// Returns whether `version` satisfies this requirement.
+    pub fn matches(&self, version: &Version) -> bool {
+        self.0.matches(version)
+    }
+}
+
+impl FromStr for VersionRequirement {
+    type Err = ParseError;
+
+    /// Parses a requirement using NPM-compatible SemVer semantics.
+    ///
+    /// Following <https://www.npmjs.com/package/semver>, a bare version such as
+    /// `"1.2.3"` is treated as `"=1.2.3"` rather than `"^1.2.3"`. An empty string is
+    /// a requirement that matches any version.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(VersionRequirement(VersionReq::any()));
+        }
+        VersionReq::parse_compat(s, Compat::Npm)
+            .map(VersionRequirement)
+            .map_err(|_| ParseError::InvalidVersionRequirement)
+    }
+}
+
+impl fmt::Display for VersionRequirement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for VersionRequirement {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionRequirement {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = String::deserialize(deserializer)?;
+        raw.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// An error raised while parsing an [`Id`] or a [`VersionRequirement`].
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    #[error("invalid bindle id")]
+    InvalidId,
+    #[error("invalid version requirement")]
+    InvalidVersionRequirement,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_id_parses_name_and_version() {
+        let id: Id = "foo/1.2.3".parse().unwrap();
+        assert_eq!(id.name(), "foo");
+        assert_eq!(id.version(), &Version::parse("1.2.3").unwrap());
+    }
+
+    #[test]
+    fn test_id_rejects_unparseable_version() {
+        assert_eq!(
+            "foo/not-a-version".parse::<Id>(),
+            Err(ParseError::InvalidId)
+        );
+        assert_eq!("no-slash-here".parse::<Id>(), Err(ParseError::InvalidId));
+    }
+
+    #[test]
+    fn test_id_sha_is_stable() {
+        let a: Id = "foo/1.2.3".parse().unwrap();
+        let b: Id = "foo/1.2.3".parse().unwrap();
+        assert_eq!(a.sha(), b.sha());
+
+        let c: Id = "foo/1.2.4".parse().unwrap();
+        assert_ne!(a.sha(), c.sha());
+    }
+
+    #[test]
+    fn test_version_requirement_npm_compat() {
+        let req: VersionRequirement = "1.2.3".parse().unwrap();
+        assert!(req.matches(&Version::parse("1.2.3").unwrap()));
+        assert!(!req.matches(&Version::parse("1.2.4").unwrap()));
+
+        let req: VersionRequirement = "^1.2".parse().unwrap();
+        assert!(req.matches(&Version::parse("1.9.0").unwrap()));
+        assert!(!req.matches(&Version::parse("2.0.0").unwrap()));
+    }
+
+    #[test]
+    fn test_version_requirement_rejects_garbage() {
+        assert!("%^&%^&%".parse::<VersionRequirement>().is_err());
+    }
+
+    #[test]
+    fn test_version_requirement_empty_matches_anything() {
+        let req: VersionRequirement = "".parse().unwrap();
+        assert!(req.matches(&Version::parse("0.0.1").unwrap()));
+        assert!(req.matches(&Version::parse("9.9.9").unwrap()));
+    }
+}
@@ -3,15 +3,17 @@ extern crate serde;
 
 mod id;
 
+pub mod dependency;
+pub mod resolver;
 pub mod search;
 pub mod server;
+pub mod signature;
 pub mod storage;
 
-pub use id::Id;
+pub use id::{Id, VersionRequirement};
 pub use search::Matches;
 pub use server::InvoiceCreateResponse;
 
-use semver::{Compat, Version, VersionReq};
 use serde::{Deserialize, Serialize};
 
 use std::collections::BTreeMap;
@@ -29,6 +31,9 @@ pub struct Invoice {
     pub parcels: Option<Vec<Parcel>>,
     // TODO: Should this be renamed "groups" or should "parcels" be renamed to "parcel"
     pub group: Option<Vec<Group>>,
+    /// Detached signatures from anyone who has vouched for this invoice. See
+    /// [`crate::signature`].
+    pub signatures: Option<Vec<signature::Signature>>,
 }
 
 impl Invoice {
@@ -52,20 +57,10 @@ impl Invoice {
         self.bindle.id.sha()
     }
 
-    /// Compare a SemVer "requirement" string to the version on this bindle
-    ///
-    /// An empty range matches anything.
-    ///
-    /// A range that fails to parse matches nothing.
-    ///
-    /// An empty version matches nothing (unless the requirement is empty)
-    ///
-    /// A version that fails to parse matches nothing (unless the requirement is empty).
-    ///
-    /// In all other cases, if the version satisfies the requirement, this returns true.
-    /// And if it fails to satisfy the requirement, this returns false.
-    fn version_in_range(&self, requirement: &str) -> bool {
-        version_compare(self.bindle.id.version(), requirement)
+    /// Returns whether this bindle's version satisfies the given requirement.
+    #[allow(dead_code)]
+    fn version_in_range(&self, requirement: &VersionRequirement) -> bool {
+        requirement.matches(self.bindle.id.version())
     }
 }
 
@@ -76,6 +71,18 @@ pub struct BindleSpec {
     pub id: Id,
     pub description: Option<String>,
     pub authors: Option<Vec<String>>,
+    pub dependencies: Option<Vec<Dependency>>,
+}
+
+/// A reference to another bindle that this one depends on, plus the version
+/// requirement it must satisfy.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct Dependency {
+    pub name: String,
+    pub version: VersionRequirement,
+    pub repositories: Option<Vec<String>>,
+    pub annotations: Option<BTreeMap<String, String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -110,43 +117,13 @@ pub struct Group {
     pub satisfied_by: Option<String>,
 }
 
-/// Check whether the given version is within the legal range.
-///
-/// An empty range matches anything.
-///
-/// A range that fails to parse matches nothing.
-///
-/// An empty version matches nothing (unless the requirement is empty)
-///
-/// A version that fails to parse matches nothing (unless the requirement is empty).
+/// Check whether the given version satisfies the given requirement.
 ///
-/// In all other cases, if the version satisfies the requirement, this returns true.
-/// And if it fails to satisfy the requirement, this returns false.
-pub fn version_compare(version: &str, requirement: &str) -> bool {
-    if requirement.is_empty() {
-        return true;
-    }
-
-    // Setting Compat::Npm follows the rules here:
-    // https://www.npmjs.com/package/semver
-    //
-    // Most importantly, the requirement "1.2.3" is treated as "= 1.2.3".
-    // Without the compat mode, "1.2.3" is treated as "^1.2.3".
-    match VersionReq::parse_compat(requirement, Compat::Npm) {
-        Ok(req) => {
-            return match Version::parse(version) {
-                Ok(ver) => req.matches(&ver),
-                Err(e) => {
-                    eprintln!("Match failed with an error: {}", e);
-                    false
-                }
-            }
-        }
-        Err(e) => {
-            eprintln!("SemVer range could not parse: {}", e);
-        }
-    }
-    false
+/// Unlike the string-based comparison this replaces, both arguments are already
+/// parsed: a [`semver::Version`] either satisfies a [`VersionRequirement`] or it
+/// doesn't, so there is no parse-failure case left to paper over with a default.
+pub fn version_compare(version: &semver::Version, requirement: &VersionRequirement) -> bool {
+    requirement.matches(version)
 }
 
 #[cfg(test)]
@@ -177,9 +154,11 @@ mod test {
                 id: "foo/1.2.3".parse().unwrap(),
                 description: Some("bar".to_owned()),
                 authors: Some(vec!["m butcher".to_owned()]),
+                dependencies: None,
             },
             parcels,
             group: None,
+            signatures: None,
         };
 
         let res = toml::to_string(&inv).unwrap();
@@ -187,7 +166,7 @@ mod test {
 
         let b = inv2.bindle;
         assert_eq!(b.id.name(), "foo".to_owned());
-        assert_eq!(b.id.version(), "1.2.3");
+        assert_eq!(b.id.version().to_string(), "1.2.3");
         assert_eq!(b.description.unwrap().as_str(), "bar");
         assert_eq!(b.authors.unwrap()[0], "m butcher".to_owned());
 
@@ -228,24 +207,26 @@ mod test {
 
     #[test]
     fn test_version_comparisons() {
+        let version = semver::Version::parse("1.2.3").unwrap();
+
         // Do not need an exhaustive list of matches -- just a sampling to make sure
         // the outer logic is correct.
         let reqs = vec!["= 1.2.3", "1.2.3", "1.2.3", "^1.1", "~1.2", ""];
 
         reqs.iter().for_each(|r| {
-            if !version_compare("1.2.3", r) {
+            let req: VersionRequirement = r.parse().unwrap();
+            if !version_compare(&version, &req) {
                 panic!("Should have passed: {}", r)
             }
         });
 
         // Again, we do not need to test the SemVer crate -- just make sure some
         // outliers and obvious cases are covered.
-        let reqs = vec!["2", "%^&%^&%"];
-        reqs.iter()
-            .for_each(|r| assert!(!version_compare("1.2.3", r)));
+        let req: VersionRequirement = "2".parse().unwrap();
+        assert!(!version_compare(&version, &req));
 
-        // Finally, test the outliers having to do with version strings
-        let vers = vec!["", "%^&%^&%"];
-        vers.iter().for_each(|v| assert!(!version_compare(v, "^1")));
+        // A requirement that fails to parse is rejected up front, rather than
+        // treated as "matches nothing" deep inside the comparison.
+        assert!("%^&%^&%".parse::<VersionRequirement>().is_err());
     }
 }
@@ -0,0 +1,265 @@
+//! Resolves an [`Invoice`]'s groups and parcel conditions into an activation set.
+//!
+//! An invoice's `group` and `parcel.conditions` fields describe a dependency graph
+//! between parcels, but on their own they are inert data -- nothing says which
+//! parcels should actually be installed for a given invoice. This module answers
+//! that question, mirroring how Cargo resolves a crate's feature/dependency graph
+//! into a concrete set of things to build.
+
+use std::collections::{HashSet, VecDeque};
+
+use thiserror::Error;
+
+use crate::{Group, Invoice, Parcel};
+
+/// An error produced while resolving an invoice's activation set.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ResolutionError {
+    /// A group named as `required`, or named in another parcel's `requires`, has no
+    /// parcel that declares membership in it.
+    #[error("group '{0}' has no member parcel")]
+    EmptyGroup(String),
+}
+
+/// Resolves the set of parcels that should be activated for `invoice`.
+///
+/// The activation set is seeded with:
+/// - every parcel that belongs to no group at all, and
+/// - every parcel whose `conditions.member_of` includes a `required` group.
+///
+/// From there, each activated parcel's `conditions.requires` is processed as a
+/// worklist of groups that must be satisfied: a group is satisfied by selecting the
+/// parcel named in its `satisfied_by`, falling back to its first member parcel if
+/// `satisfied_by` is unset. Newly selected parcels are pushed back onto the
+/// worklist so their own `requires` gets processed in turn. Groups are tracked as
+/// visited so a requirement cycle terminates instead of looping forever.
+///
+/// The returned `Vec` preserves the order parcels were activated in.
+pub fn resolve(invoice: &Invoice) -> Result<Vec<&Parcel>, ResolutionError> {
+    let parcels = invoice.parcels.as_deref().unwrap_or(&[]);
+    let groups = invoice.group.as_deref().unwrap_or(&[]);
+
+    let mut activated = Vec::new();
+    let mut activated_names: HashSet<&str> = HashSet::new();
+    let mut worklist = VecDeque::new();
+
+    for parcel in parcels {
+        let member_of = parcel
+            .conditions
+            .as_ref()
+            .and_then(|c| c.member_of.as_ref());
+        let in_any_group = member_of.map(|g| !g.is_empty()).unwrap_or(false);
+        let in_required_group = member_of
+            .map(|names| names.iter().any(|name| is_required(groups, name)))
+            .unwrap_or(false);
+
+        if !in_any_group || in_required_group {
+            activate_parcel(parcel, &mut activated, &mut activated_names, &mut worklist);
+        }
+    }
+
+    // Every `required` group must have at least one member, even if none of its
+    // members were already pulled in above (e.g. it isn't required by name yet but
+    // is marked `required` on the group itself).
+    for group in groups.iter().filter(|g| g.required.unwrap_or(false)) {
+        if !parcels.iter().any(|p| parcel_member_of(p, &group.name)) {
+            return Err(ResolutionError::EmptyGroup(group.name.clone()));
+        }
+    }
+
+    let mut visited_groups: HashSet<&str> = HashSet::new();
+    while let Some(parcel) = worklist.pop_front() {
+        let requires = match parcel.conditions.as_ref().and_then(|c| c.requires.as_ref()) {
+            Some(requires) => requires,
+            None => continue,
+        };
+        for group_name in requires {
+            if !visited_groups.insert(group_name.as_str()) {
+                continue;
+            }
+            let group = groups.iter().find(|g| &g.name == group_name);
+            let selected = select_group_member(parcels, group, group_name)?;
+            activate_parcel(selected, &mut activated, &mut activated_names, &mut worklist);
+        }
+    }
+
+    Ok(activated)
+}
+
+fn activate_parcel<'a>(
+    parcel: &'a Parcel,
+    activated: &mut Vec<&'a Parcel>,
+    activated_names: &mut HashSet<&'a str>,
+    worklist: &mut VecDeque<&'a Parcel>,
+) {
+    if activated_names.insert(parcel.label.name.as_str()) {
+        activated.push(parcel);
+        worklist.push_back(parcel);
+    }
+}
+
+fn parcel_member_of(parcel: &Parcel, group_name: &str) -> bool {
+    parcel
+        .conditions
+        .as_ref()
+        .and_then(|c| c.member_of.as_ref())
+        .map(|names| names.iter().any(|n| n == group_name))
+        .unwrap_or(false)
+}
+
+fn is_required(groups: &[Group], name: &str) -> bool {
+    groups
+        .iter()
+        .any(|g| g.name == name && g.required.unwrap_or(false))
+}
+
+/// Picks the parcel that satisfies `group_name`: the group's `satisfied_by` parcel
+/// if it names one, else the first parcel declaring membership in the group.
+fn select_group_member<'a>(
+    parcels: &'a [Parcel],
+    group: Option<&Group>,
+    group_name: &str,
+) -> Result<&'a Parcel, ResolutionError> {
+    if let Some(satisfied_by) = group.and_then(|g| g.satisfied_by.as_deref()) {
+        if let Some(parcel) = parcels.iter().find(|p| p.label.name == satisfied_by) {
+            return Ok(parcel);
+        }
+    }
+    parcels
+        .iter()
+        .find(|p| parcel_member_of(p, group_name))
+        .ok_or_else(|| ResolutionError::EmptyGroup(group_name.to_owned()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Condition, Label};
+
+    fn parcel(name: &str, member_of: Option<Vec<&str>>, requires: Option<Vec<&str>>) -> Parcel {
+        Parcel {
+            label: Label {
+                sha256: format!("{}-sha", name),
+                media_type: "application/octet-stream".to_owned(),
+                name: name.to_owned(),
+                size: None,
+                annotations: None,
+            },
+            conditions: if member_of.is_some() || requires.is_some() {
+                Some(Condition {
+                    member_of: member_of.map(|v| v.into_iter().map(String::from).collect()),
+                    requires: requires.map(|v| v.into_iter().map(String::from).collect()),
+                })
+            } else {
+                None
+            },
+        }
+    }
+
+    fn group(name: &str, required: bool, satisfied_by: Option<&str>) -> Group {
+        Group {
+            name: name.to_owned(),
+            required: Some(required),
+            satisfied_by: satisfied_by.map(String::from),
+        }
+    }
+
+    fn invoice(parcels: Vec<Parcel>, groups: Vec<Group>) -> Invoice {
+        Invoice {
+            bindle_version: crate::BINDLE_VERSION_1.to_owned(),
+            yanked: None,
+            bindle: crate::BindleSpec {
+                id: "test/1.0.0".parse().unwrap(),
+                description: None,
+                authors: None,
+                dependencies: None,
+            },
+            annotations: None,
+            parcels: Some(parcels),
+            group: Some(groups),
+            signatures: None,
+        }
+    }
+
+    #[test]
+    fn test_ungrouped_parcels_are_always_activated() {
+        let inv = invoice(vec![parcel("base", None, None)], vec![]);
+        let activated = resolve(&inv).unwrap();
+        assert_eq!(activated.len(), 1);
+        assert_eq!(activated[0].label.name, "base");
+    }
+
+    #[test]
+    fn test_required_group_member_is_activated() {
+        let inv = invoice(
+            vec![parcel("server", Some(vec!["backends"]), None)],
+            vec![group("backends", true, None)],
+        );
+        let activated = resolve(&inv).unwrap();
+        assert_eq!(activated.len(), 1);
+        assert_eq!(activated[0].label.name, "server");
+    }
+
+    #[test]
+    fn test_optional_group_member_is_not_activated() {
+        let inv = invoice(
+            vec![parcel("extra", Some(vec!["optional"]), None)],
+            vec![group("optional", false, None)],
+        );
+        let activated = resolve(&inv).unwrap();
+        assert!(activated.is_empty());
+    }
+
+    #[test]
+    fn test_requires_pulls_in_satisfied_by_parcel() {
+        let inv = invoice(
+            vec![
+                parcel("app", None, Some(vec!["backends"])),
+                parcel("sqlite", Some(vec!["backends"]), None),
+                parcel("postgres", Some(vec!["backends"]), None),
+            ],
+            vec![group("backends", false, Some("postgres"))],
+        );
+        let activated = resolve(&inv).unwrap();
+        let names: Vec<_> = activated.iter().map(|p| p.label.name.as_str()).collect();
+        assert_eq!(names, vec!["app", "postgres"]);
+    }
+
+    #[test]
+    fn test_requires_falls_back_to_first_member() {
+        let inv = invoice(
+            vec![
+                parcel("app", None, Some(vec!["backends"])),
+                parcel("sqlite", Some(vec!["backends"]), None),
+            ],
+            vec![group("backends", false, None)],
+        );
+        let activated = resolve(&inv).unwrap();
+        let names: Vec<_> = activated.iter().map(|p| p.label.name.as_str()).collect();
+        assert_eq!(names, vec!["app", "sqlite"]);
+    }
+
+    #[test]
+    fn test_empty_required_group_errors() {
+        let inv = invoice(vec![], vec![group("backends", true, None)]);
+        let err = resolve(&inv).unwrap_err();
+        assert_eq!(err, ResolutionError::EmptyGroup("backends".to_owned()));
+    }
+
+    #[test]
+    fn test_cyclic_requirements_terminate() {
+        let inv = invoice(
+            vec![
+                parcel("a", Some(vec!["g-a"]), Some(vec!["g-b"])),
+                parcel("b", Some(vec!["g-b"]), Some(vec!["g-a"])),
+            ],
+            vec![
+                group("g-a", true, Some("a")),
+                group("g-b", false, Some("b")),
+            ],
+        );
+        let activated = resolve(&inv).unwrap();
+        let names: Vec<_> = activated.iter().map(|p| p.label.name.as_str()).collect();
+        assert_eq!(names, vec!["a", "b"]);
+    }
+}
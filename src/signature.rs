@@ -0,0 +1,166 @@
+//! Detached ed25519 signatures over an invoice's canonical serialization.
+//!
+//! Invoices are trusted today purely by content hash: storage confirms a parcel's
+//! bytes match its label (see [`crate::storage::digest`]), but nothing says who
+//! vouches for the invoice itself. A [`Signature`] binds a signer's public key over
+//! the invoice body, so verifying it answers "who signed this" rather than just
+//! "is this internally consistent". Because the signed bytes include every
+//! parcel's `sha256`, a verified invoice transitively vouches for its parcels'
+//! content too -- there's no separate "sign the parcel" step.
+
+use ed25519_dalek::{Keypair, PublicKey, Signature as Ed25519Signature, Signer, Verifier};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::Invoice;
+
+/// A detached signature over an invoice, binding a signer's public key to the
+/// invoice body at the time it was signed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct Signature {
+    /// Base64-encoded ed25519 public key of the signer.
+    pub key: String,
+    /// Base64-encoded ed25519 signature bytes.
+    pub signature: String,
+}
+
+/// An error raised while signing or verifying an invoice.
+#[derive(Error, Debug)]
+pub enum SignatureError {
+    #[error("invoice could not be canonically serialized")]
+    Canonicalization(#[from] toml::ser::Error),
+    #[error("signer's public key is malformed")]
+    InvalidKey,
+    #[error("signature is malformed")]
+    InvalidSignature,
+}
+
+/// The result of checking every [`Signature`] attached to an invoice.
+#[derive(Debug, Default, Clone)]
+pub struct VerificationReport {
+    /// Base64-encoded public keys whose signature verified correctly.
+    pub verified_keys: Vec<String>,
+    /// Base64-encoded public keys present on the invoice whose signature did not
+    /// verify (malformed key/signature, or a signature over different bytes).
+    pub invalid_keys: Vec<String>,
+}
+
+impl VerificationReport {
+    /// Whether `key` (base64-encoded) produced a verified signature.
+    pub fn is_signed_by(&self, key: &str) -> bool {
+        self.verified_keys.iter().any(|k| k == key)
+    }
+}
+
+/// Produces the exact bytes that are signed and verified: the invoice, serialized
+/// to TOML, with its own `signatures` field cleared first. Clearing the field
+/// first means adding a second signature never invalidates the first.
+pub fn canonical_bytes(invoice: &Invoice) -> Result<Vec<u8>, SignatureError> {
+    let mut unsigned = invoice.clone();
+    unsigned.signatures = None;
+    Ok(toml::to_string(&unsigned)?.into_bytes())
+}
+
+/// Signs `invoice` with `keypair`, producing a new detached [`Signature`].
+///
+/// This does not attach the signature to the invoice; callers append the result to
+/// `invoice.signatures` themselves, since an invoice may collect signatures from
+/// more than one signer.
+pub fn sign(invoice: &Invoice, keypair: &Keypair) -> Result<Signature, SignatureError> {
+    let bytes = canonical_bytes(invoice)?;
+    let signature = keypair.sign(&bytes);
+    Ok(Signature {
+        key: base64::encode(keypair.public.as_bytes()),
+        signature: base64::encode(signature.to_bytes()),
+    })
+}
+
+/// Verifies every signature attached to `invoice` against its canonical bytes.
+pub fn verify_all(invoice: &Invoice) -> Result<VerificationReport, SignatureError> {
+    let bytes = canonical_bytes(invoice)?;
+    let mut report = VerificationReport::default();
+    for sig in invoice.signatures.as_deref().unwrap_or(&[]) {
+        if verify_one(&bytes, sig).is_ok() {
+            report.verified_keys.push(sig.key.clone());
+        } else {
+            report.invalid_keys.push(sig.key.clone());
+        }
+    }
+    Ok(report)
+}
+
+fn verify_one(bytes: &[u8], sig: &Signature) -> Result<(), SignatureError> {
+    let key_bytes = base64::decode(&sig.key).map_err(|_| SignatureError::InvalidKey)?;
+    let public = PublicKey::from_bytes(&key_bytes).map_err(|_| SignatureError::InvalidKey)?;
+    let sig_bytes = base64::decode(&sig.signature).map_err(|_| SignatureError::InvalidSignature)?;
+    let signature =
+        Ed25519Signature::from_bytes(&sig_bytes).map_err(|_| SignatureError::InvalidSignature)?;
+    public
+        .verify(bytes, &signature)
+        .map_err(|_| SignatureError::InvalidSignature)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{BindleSpec, BINDLE_VERSION_1};
+    use ed25519_dalek::Keypair;
+    use rand::rngs::OsRng;
+
+    fn invoice() -> Invoice {
+        Invoice {
+            bindle_version: BINDLE_VERSION_1.to_owned(),
+            yanked: None,
+            bindle: BindleSpec {
+                id: "foo/1.0.0".parse().unwrap(),
+                description: None,
+                authors: None,
+                dependencies: None,
+            },
+            annotations: None,
+            parcels: None,
+            group: None,
+            signatures: None,
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify_round_trip() {
+        let mut csprng = OsRng {};
+        let keypair = Keypair::generate(&mut csprng);
+
+        let mut inv = invoice();
+        let sig = sign(&inv, &keypair).unwrap();
+        inv.signatures = Some(vec![sig]);
+
+        let report = verify_all(&inv).unwrap();
+        assert_eq!(report.verified_keys.len(), 1);
+        assert!(report.invalid_keys.is_empty());
+        assert!(report.is_signed_by(&base64::encode(keypair.public.as_bytes())));
+    }
+
+    #[test]
+    fn test_tampering_invalidates_signature() {
+        let mut csprng = OsRng {};
+        let keypair = Keypair::generate(&mut csprng);
+
+        let mut inv = invoice();
+        let sig = sign(&inv, &keypair).unwrap();
+        inv.signatures = Some(vec![sig]);
+
+        // Tamper with the invoice after signing.
+        inv.bindle.description = Some("tampered".to_owned());
+
+        let report = verify_all(&inv).unwrap();
+        assert!(report.verified_keys.is_empty());
+        assert_eq!(report.invalid_keys.len(), 1);
+    }
+
+    #[test]
+    fn test_unsigned_invoice_verifies_with_no_signers() {
+        let report = verify_all(&invoice()).unwrap();
+        assert!(report.verified_keys.is_empty());
+        assert!(report.invalid_keys.is_empty());
+    }
+}
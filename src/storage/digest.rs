@@ -0,0 +1,58 @@
+//! Helpers for verifying a parcel's content as it is streamed into storage.
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Wraps an `AsyncRead`, feeding every byte that passes through it into a running
+/// SHA-256 hash and a byte counter.
+///
+/// This lets [`super::Storage::create_parcel`] implementations compute a parcel's
+/// digest and size in a single pass over the stream, rather than hashing the data
+/// and then re-reading it (or buffering it all in memory) to check it.
+pub(crate) struct DigestingReader<'a, R> {
+    reader: &'a mut R,
+    hasher: Sha256,
+    bytes_read: u64,
+}
+
+impl<'a, R: AsyncRead + Unpin> DigestingReader<'a, R> {
+    pub(crate) fn new(reader: &'a mut R) -> Self {
+        DigestingReader {
+            reader,
+            hasher: Sha256::new(),
+            bytes_read: 0,
+        }
+    }
+
+    /// The number of bytes read so far.
+    pub(crate) fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    /// The lowercase hex-encoded SHA-256 digest of everything read so far.
+    ///
+    /// This consumes the reader because `Sha256::finalize` consumes the hasher.
+    pub(crate) fn finalize_hex(self) -> String {
+        hex::encode(self.hasher.finalize())
+    }
+}
+
+impl<'a, R: AsyncRead + Unpin> AsyncRead for DigestingReader<'a, R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let start = buf.filled().len();
+        let res = Pin::new(&mut *self.reader).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = res {
+            let filled = &buf.filled()[start..];
+            self.hasher.update(filled);
+            self.bytes_read += filled.len() as u64;
+        }
+        res
+    }
+}
@@ -0,0 +1,283 @@
+//! A [`Storage`] implementation backed by a plain directory tree on local disk.
+
+use std::convert::TryInto;
+use std::path::PathBuf;
+
+use tokio::fs;
+use tokio::io::AsyncRead;
+
+use super::digest::DigestingReader;
+use super::{Result, SignaturePolicy, Storage, StorageError};
+use crate::id::ParseError;
+use crate::{Id, Invoice, Label};
+
+const INVOICE_TOML: &str = "invoice.toml";
+const PARCEL_DAT: &str = "parcel.dat";
+const LABEL_TOML: &str = "label.toml";
+
+/// Stores invoices and parcels as files underneath a root directory.
+///
+/// Invoices are kept at `<root>/invoices/<canonical name>/invoice.toml`, and
+/// parcels at `<root>/parcels/<sha256>/parcel.dat` alongside their
+/// `label.toml`.
+#[derive(Clone, Debug)]
+pub struct FileStorage {
+    root: PathBuf,
+    signature_policy: SignaturePolicy,
+}
+
+impl FileStorage {
+    /// Creates a new file-backed store rooted at the given directory.
+    ///
+    /// The directory is not required to exist yet; it is created lazily as
+    /// invoices and parcels are written. Invoices are accepted regardless of
+    /// whether they are signed; use [`FileStorage::with_signature_policy`] to
+    /// require signatures.
+    pub fn new<P: Into<PathBuf>>(root: P) -> Self {
+        FileStorage {
+            root: root.into(),
+            signature_policy: SignaturePolicy::default(),
+        }
+    }
+
+    /// Sets the policy this store enforces on invoice signatures at creation time.
+    pub fn with_signature_policy(mut self, policy: SignaturePolicy) -> Self {
+        self.signature_policy = policy;
+        self
+    }
+
+    fn invoice_dir(&self, id: &Id) -> PathBuf {
+        self.root.join("invoices").join(id.sha())
+    }
+
+    fn parcel_dir(&self, sha256: &str) -> PathBuf {
+        self.root.join("parcels").join(sha256)
+    }
+}
+
+#[async_trait::async_trait]
+impl Storage for FileStorage {
+    async fn create_invoice(&self, inv: &Invoice) -> Result<Vec<Label>> {
+        self.signature_policy.enforce(inv)?;
+
+        let dir = self.invoice_dir(&inv.bindle.id);
+        fs::create_dir_all(&dir).await?;
+
+        let raw = toml::to_string_pretty(inv)?;
+        fs::write(dir.join(INVOICE_TOML), raw).await?;
+
+        let mut missing = Vec::new();
+        if let Some(parcels) = &inv.parcels {
+            for parcel in parcels {
+                if !self.parcel_dir(&parcel.label.sha256).join(PARCEL_DAT).exists() {
+                    missing.push(parcel.label.clone());
+                }
+            }
+        }
+        Ok(missing)
+    }
+
+    async fn get_invoice<I>(&self, id: I) -> Result<Invoice>
+    where
+        I: TryInto<Id, Error = ParseError> + Send,
+    {
+        let inv = self.get_yanked_invoice(id).await?;
+        if inv.yanked.unwrap_or(false) {
+            return Err(StorageError::Yanked);
+        }
+        Ok(inv)
+    }
+
+    async fn get_yanked_invoice<I>(&self, id: I) -> Result<Invoice>
+    where
+        I: TryInto<Id, Error = ParseError> + Send,
+    {
+        let id: Id = id.try_into()?;
+        let path = self.invoice_dir(&id).join(INVOICE_TOML);
+        let raw = fs::read_to_string(path)
+            .await
+            .map_err(|_| StorageError::NotFound)?;
+        Ok(toml::from_str(&raw)?)
+    }
+
+    async fn yank_invoice<I>(&self, id: I) -> Result<()>
+    where
+        I: TryInto<Id, Error = ParseError> + Send,
+    {
+        let id: Id = id.try_into()?;
+        let path = self.invoice_dir(&id).join(INVOICE_TOML);
+        let raw = fs::read_to_string(&path)
+            .await
+            .map_err(|_| StorageError::NotFound)?;
+        let mut inv: Invoice = toml::from_str(&raw)?;
+        inv.yanked = Some(true);
+        fs::write(&path, toml::to_string_pretty(&inv)?).await?;
+        Ok(())
+    }
+
+    async fn create_parcel<R: AsyncRead + Unpin + Send + Sync>(
+        &self,
+        label: &Label,
+        data: &mut R,
+    ) -> Result<()> {
+        let dir = self.parcel_dir(&label.sha256);
+        fs::create_dir_all(&dir).await?;
+
+        let data_path = dir.join(PARCEL_DAT);
+        let tmp_path = dir.join(format!("{}.partial", PARCEL_DAT));
+
+        let mut out = fs::File::create(&tmp_path).await?;
+        let mut reader = DigestingReader::new(data);
+        let copy_result = tokio::io::copy(&mut reader, &mut out).await;
+        let bytes_read = reader.bytes_read();
+        let digest = reader.finalize_hex();
+
+        // Surface IO errors from the copy itself before checking the digest/size,
+        // but make sure we clean up the partial file either way.
+        if let Err(e) = copy_result {
+            let _ = fs::remove_file(&tmp_path).await;
+            return Err(StorageError::IO(e));
+        }
+
+        if digest != label.sha256 {
+            let _ = fs::remove_file(&tmp_path).await;
+            return Err(StorageError::DigestMismatch);
+        }
+        if let Some(expected_size) = label.size {
+            if expected_size < 0 || bytes_read != expected_size as u64 {
+                let _ = fs::remove_file(&tmp_path).await;
+                return Err(StorageError::SizeMismatch);
+            }
+        }
+
+        fs::rename(&tmp_path, &data_path).await?;
+        fs::write(dir.join(LABEL_TOML), toml::to_string_pretty(label)?).await?;
+        Ok(())
+    }
+
+    async fn get_invoice_versions(&self, name: &str) -> Result<Vec<Id>> {
+        let invoices_dir = self.root.join("invoices");
+        let mut read_dir = match fs::read_dir(&invoices_dir).await {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(StorageError::IO(e)),
+        };
+
+        let mut versions = Vec::new();
+        while let Some(entry) = read_dir.next_entry().await? {
+            let raw = match fs::read_to_string(entry.path().join(INVOICE_TOML)).await {
+                Ok(raw) => raw,
+                Err(_) => continue,
+            };
+            let inv: Invoice = match toml::from_str(&raw) {
+                Ok(inv) => inv,
+                Err(_) => continue,
+            };
+            if inv.yanked.unwrap_or(false) {
+                continue;
+            }
+            if inv.bindle.id.name() == name {
+                versions.push(inv.bindle.id);
+            }
+        }
+        Ok(versions)
+    }
+
+    async fn get_parcel(&self, parcel_id: &str) -> Result<Box<dyn AsyncRead + Unpin + Send>> {
+        let path = self.parcel_dir(parcel_id).join(PARCEL_DAT);
+        let file = fs::File::open(path).await.map_err(|_| StorageError::NotFound)?;
+        Ok(Box::new(file))
+    }
+
+    async fn get_label(&self, parcel_id: &str) -> Result<Label> {
+        let path = self.parcel_dir(parcel_id).join(LABEL_TOML);
+        let raw = fs::read_to_string(path)
+            .await
+            .map_err(|_| StorageError::NotFound)?;
+        Ok(toml::from_str(&raw)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use super::super::test_common::{sample_invoice, sample_label};
+    use super::*;
+
+    fn temp_storage(test_name: &str) -> FileStorage {
+        let root = std::env::temp_dir().join(format!(
+            "bindle-file-storage-test-{}-{}",
+            test_name,
+            std::process::id()
+        ));
+        FileStorage::new(root)
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get_invoice_round_trips() {
+        let storage = temp_storage("round-trip");
+        let inv = sample_invoice();
+
+        storage.create_invoice(&inv).await.unwrap();
+        let fetched = storage.get_invoice(inv.name()).await.unwrap();
+        assert_eq!(fetched.bindle.id, inv.bindle.id);
+    }
+
+    #[tokio::test]
+    async fn test_create_parcel_rejects_digest_mismatch() {
+        let storage = temp_storage("digest-mismatch");
+        let mut label = sample_label();
+        label.sha256 = "0".repeat(64);
+
+        let mut data = Cursor::new(b"hello world".to_vec());
+        let err = storage.create_parcel(&label, &mut data).await.unwrap_err();
+        assert!(matches!(err, StorageError::DigestMismatch));
+    }
+
+    #[tokio::test]
+    async fn test_create_parcel_rejects_size_mismatch() {
+        let storage = temp_storage("size-mismatch");
+        let payload = b"hello world".to_vec();
+        let mut label = sample_label();
+        label.sha256 = {
+            use sha2::{Digest, Sha256};
+            let mut hasher = Sha256::new();
+            hasher.update(&payload);
+            hex::encode(hasher.finalize())
+        };
+        label.size = Some((payload.len() as i64) + 1);
+
+        let mut data = Cursor::new(payload);
+        let err = storage.create_parcel(&label, &mut data).await.unwrap_err();
+        assert!(matches!(err, StorageError::SizeMismatch));
+    }
+
+    #[tokio::test]
+    async fn test_create_parcel_accepts_matching_digest_and_size() {
+        let storage = temp_storage("digest-match");
+        let payload = b"hello world".to_vec();
+        let mut label = sample_label();
+        label.sha256 = {
+            use sha2::{Digest, Sha256};
+            let mut hasher = Sha256::new();
+            hasher.update(&payload);
+            hex::encode(hasher.finalize())
+        };
+        label.size = Some(payload.len() as i64);
+
+        let mut data = Cursor::new(payload);
+        storage.create_parcel(&label, &mut data).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_signature_policy_rejects_unsigned_invoice() {
+        let storage =
+            temp_storage("reject-unsigned").with_signature_policy(SignaturePolicy::RejectUnsigned);
+        let err = storage
+            .create_invoice(&sample_invoice())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StorageError::SignatureInvalid));
+    }
+}
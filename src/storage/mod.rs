@@ -11,6 +11,8 @@ use tokio::io::AsyncRead;
 use crate::id::ParseError;
 use crate::Id;
 
+pub(crate) mod digest;
+
 pub type Result<T> = core::result::Result<T, StorageError>;
 
 #[async_trait::async_trait]
@@ -33,6 +35,12 @@ pub trait Storage {
     async fn yank_invoice<I>(&self, id: I) -> Result<()>
     where
         I: TryInto<Id, Error = ParseError> + Send;
+    /// Lists the `Id`s of every non-yanked invoice stored under the given bindle
+    /// name, in no particular order.
+    ///
+    /// This is how callers discover what versions of a named bindle exist, e.g. to
+    /// pick one that satisfies a [`crate::VersionRequirement`].
+    async fn get_invoice_versions(&self, name: &str) -> Result<Vec<Id>>;
     async fn create_parcel<R: AsyncRead + Unpin + Send + Sync>(
         &self,
         label: &super::Label,
@@ -63,6 +71,12 @@ pub enum StorageError {
     InvalidId,
     #[error("digest does not match")]
     DigestMismatch,
+    #[error("size does not match")]
+    SizeMismatch,
+    #[error("invoice signature is missing or does not verify")]
+    SignatureInvalid,
+    #[error("invoice is not signed by a trusted key")]
+    Untrusted,
 
     // TODO: Investigate how to make this more helpful
     #[error("resource is malformed")]
@@ -75,6 +89,55 @@ impl From<crate::id::ParseError> for StorageError {
     fn from(e: crate::id::ParseError) -> StorageError {
         match e {
             crate::id::ParseError::InvalidId => StorageError::InvalidId,
+            crate::id::ParseError::InvalidVersionRequirement => StorageError::InvalidId,
+        }
+    }
+}
+
+/// Governs whether [`Storage::create_invoice`] requires an invoice to carry a
+/// valid signature before accepting it.
+#[derive(Debug, Clone, Default)]
+pub enum SignaturePolicy {
+    /// Accept any invoice, verifying and recording whatever signatures it carries
+    /// without requiring one.
+    #[default]
+    AcceptAndRecord,
+    /// Reject an invoice that carries no signature that verifies correctly.
+    RejectUnsigned,
+    /// Reject an invoice unless it carries a verified signature from one of these
+    /// base64-encoded public keys.
+    RejectUnknownKey { trusted_keys: Vec<String> },
+}
+
+impl SignaturePolicy {
+    /// Checks `invoice` against this policy, returning the verification report so
+    /// callers can record it (e.g. log who signed) alongside the invoice.
+    pub(crate) fn enforce(
+        &self,
+        invoice: &super::Invoice,
+    ) -> Result<crate::signature::VerificationReport> {
+        let report =
+            crate::signature::verify_all(invoice).map_err(|_| StorageError::SignatureInvalid)?;
+        match self {
+            SignaturePolicy::AcceptAndRecord => Ok(report),
+            SignaturePolicy::RejectUnsigned => {
+                if report.verified_keys.is_empty() {
+                    Err(StorageError::SignatureInvalid)
+                } else {
+                    Ok(report)
+                }
+            }
+            SignaturePolicy::RejectUnknownKey { trusted_keys } => {
+                if report
+                    .verified_keys
+                    .iter()
+                    .any(|key| trusted_keys.contains(key))
+                {
+                    Ok(report)
+                } else {
+                    Err(StorageError::Untrusted)
+                }
+            }
         }
     }
 }
@@ -0,0 +1,35 @@
+//! Shared fixtures for storage backend tests.
+
+use crate::{BindleSpec, Invoice, Label, Parcel, BINDLE_VERSION_1};
+
+/// A minimal invoice with a single parcel, useful as a starting point for storage
+/// backend tests that don't care about the specifics of the invoice body.
+pub(crate) fn sample_invoice() -> Invoice {
+    Invoice {
+        bindle_version: BINDLE_VERSION_1.to_owned(),
+        yanked: None,
+        bindle: BindleSpec {
+            id: "sample/1.0.0".parse().unwrap(),
+            description: None,
+            authors: None,
+            dependencies: None,
+        },
+        annotations: None,
+        parcels: Some(vec![Parcel {
+            label: sample_label(),
+            conditions: None,
+        }]),
+        group: None,
+        signatures: None,
+    }
+}
+
+pub(crate) fn sample_label() -> Label {
+    Label {
+        sha256: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855".to_owned(),
+        media_type: "application/octet-stream".to_owned(),
+        name: "sample.dat".to_owned(),
+        size: Some(0),
+        annotations: None,
+    }
+}